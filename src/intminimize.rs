@@ -1,24 +1,33 @@
 use std::cmp::min;
+use std::collections::HashMap;
 
 const SMALL: u64 = 5;
 
-struct Minimizer<'a, F: 'a> {
+/// Drives a single `best` candidate of type `C` toward a lower `key`-score
+/// while keeping a user criterion satisfied, short-circuiting on scores that
+/// can't possibly improve on `best`.
+struct Minimizer<'a, C, K, F: 'a> {
     criterion: &'a mut F,
-    best: u64,
+    key: K,
+    best: C,
 }
 
-impl<'a, F, T> Minimizer<'a, F>
+impl<'a, C, K, F, T> Minimizer<'a, C, K, F>
 where
-    F: 'a + FnMut(u64) -> Result<bool, T>,
+    C: Clone,
+    K: Fn(&C) -> u64,
+    F: 'a + FnMut(&C) -> Result<bool, T>,
 {
-    fn test(&mut self, candidate: u64) -> Result<bool, T> {
-        if candidate == self.best {
+    fn test(&mut self, candidate: C) -> Result<bool, T> {
+        let candidate_score = (self.key)(&candidate);
+        let best_score = (self.key)(&self.best);
+        if candidate_score == best_score {
             return Ok(true);
         }
-        if candidate > self.best {
+        if candidate_score > best_score {
             return Ok(false);
         }
-        let result = (self.criterion)(candidate)?;
+        let result = (self.criterion)(&candidate)?;
         if result {
             self.best = candidate;
         }
@@ -27,49 +36,83 @@ where
 
     fn modify<G>(&mut self, g: G) -> Result<bool, T>
     where
-        G: Fn(u64) -> u64,
+        G: Fn(&C) -> C,
     {
-        let x = g(self.best);
+        let x = g(&self.best);
         self.test(x)
     }
 }
 
-pub fn minimize_integer<F, T>(start: u64, mut criterion: F) -> Result<u64, T>
+fn identity(x: &u64) -> u64 {
+    *x
+}
+
+pub fn minimize_integer<F, T>(start: u64, criterion: F) -> Result<u64, T>
 where
     F: FnMut(u64) -> Result<bool, T>,
 {
-    if start == 0 {
+    minimize_integer_towards(start, 0, criterion)
+}
+
+/// Like `minimize_integer`, but shrinks the *distance* to `target` instead
+/// of shrinking toward zero. Useful when a test knows a "natural" baseline
+/// (a loop bound, a buffer capacity, a previously-seen good input) that's a
+/// more meaningful pivot than zero.
+///
+/// `minimize_integer` is just the `target == 0` special case of this.
+pub fn minimize_integer_towards<F, T>(
+    start: u64,
+    target: u64,
+    mut criterion: F,
+) -> Result<u64, T>
+where
+    F: FnMut(u64) -> Result<bool, T>,
+{
+    let on_positive_side = start >= target;
+    let to_value = move |d: u64| -> u64 {
+        if on_positive_side {
+            target + d
+        } else {
+            target - d
+        }
+    };
+
+    let distance = start.abs_diff(target);
+
+    if distance == 0 {
         return Ok(start);
     }
 
-    for i in 0..min(start, SMALL) {
-        if criterion(i)? {
-            return Ok(i);
+    for i in 0..min(distance, SMALL) {
+        if criterion(to_value(i))? {
+            return Ok(to_value(i));
         }
     }
-    if start <= SMALL {
+    if distance <= SMALL {
         return Ok(start);
     }
 
+    let mut wrapped = |d: &u64| -> Result<bool, T> { criterion(to_value(*d)) };
     let mut minimizer = Minimizer {
-        best: start,
-        criterion: &mut criterion,
+        best: distance,
+        key: identity,
+        criterion: &mut wrapped,
     };
 
     loop {
-        if !minimizer.modify(|x| x >> 1)? {
+        if !minimizer.modify(|x| *x >> 1)? {
             break;
         }
     }
 
     for i in 0..64 {
-        minimizer.modify(|x| x ^ (1 << i))?;
+        minimizer.modify(|x| *x ^ (1 << i))?;
     }
 
     assert!(minimizer.best >= SMALL);
 
-    if !minimizer.modify(|x| x - 1)? {
-        return Ok(minimizer.best);
+    if !minimizer.modify(|x| *x - 1)? {
+        return Ok(to_value(minimizer.best));
     }
 
     let mut lo = 0;
@@ -83,9 +126,256 @@ where
         }
     }
 
+    Ok(to_value(minimizer.best))
+}
+
+/// Generic shrink driver for any `T` whose "simplicity" can be projected
+/// onto a `u64` score.
+///
+/// `key` scores a candidate (lower is simpler), and `rebuild` materializes a
+/// concrete `T` for a given score, based on the current best (e.g. to carry
+/// over the parts of `T` that the score doesn't capture). This runs the same
+/// halving / bit-flip / binary-search passes as `minimize_integer`, but on
+/// the projected score, rebuilding and re-checking `criterion` at each step.
+/// Because multiple `T` can share a score, ties are resolved the way
+/// `cmp::min_by` resolves them: the first candidate reaching a given score
+/// wins, so `criterion` is only re-run when the score strictly improves.
+pub fn minimize_by<T, Key, Rebuild, F, E>(
+    start: T,
+    key: Key,
+    rebuild: Rebuild,
+    mut criterion: F,
+) -> Result<T, E>
+where
+    T: Clone,
+    Key: Fn(&T) -> u64,
+    Rebuild: Fn(&T, u64) -> T,
+    F: FnMut(&T) -> Result<bool, E>,
+{
+    let start_score = key(&start);
+    if start_score == 0 {
+        return Ok(start);
+    }
+
+    for i in 0..min(start_score, SMALL) {
+        let candidate = rebuild(&start, i);
+        if criterion(&candidate)? {
+            return Ok(candidate);
+        }
+    }
+    if start_score <= SMALL {
+        return Ok(start);
+    }
+
+    let mut wrapped = |candidate: &T| criterion(candidate);
+    let mut minimizer = Minimizer {
+        best: start,
+        key: &key,
+        criterion: &mut wrapped,
+    };
+
+    loop {
+        if !minimizer.modify(|x| rebuild(x, key(x) >> 1))? {
+            break;
+        }
+    }
+
+    for i in 0..64 {
+        minimizer.modify(|x| rebuild(x, key(x) ^ (1 << i)))?;
+    }
+
+    // Unlike `minimize_integer`, we can't assert `key(&minimizer.best) >=
+    // SMALL` here: the opening scan only rules out scores below `SMALL`
+    // when rebuilt from `start`, and `rebuild` is free to read non-score
+    // state from `cur` that differs from `start` by the time we get here,
+    // so a lower score can still turn out to satisfy `criterion`.
+
+    if !minimizer.modify(|x| rebuild(x, key(x) - 1))? {
+        return Ok(minimizer.best);
+    }
+
+    let mut lo = 0;
+    let mut hi = key(&minimizer.best);
+    while lo + 1 < hi {
+        let mid = lo + (hi - lo) / 2;
+        let candidate = rebuild(&minimizer.best, mid);
+        if minimizer.test(candidate)? {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
     Ok(minimizer.best)
 }
 
+/// Shrinks a failing `Vec<u64>` lexicographically: fewer elements first,
+/// then smaller elements, while keeping `criterion` true over the whole
+/// vector.
+///
+/// Repeatedly sweeps the vector trying, in order, to drop individual
+/// elements and shrink each surviving element in place via
+/// `minimize_integer`, looping until a full sweep makes no change. Since the
+/// same candidate vector can be revisited across these passes, `criterion`
+/// results are memoized to keep the number of evaluations down.
+pub fn minimize_integers<F, T>(start: Vec<u64>, mut criterion: F) -> Result<Vec<u64>, T>
+where
+    F: FnMut(&[u64]) -> Result<bool, T>,
+{
+    let mut cache: HashMap<Vec<u64>, bool> = HashMap::new();
+    let mut check = |v: &[u64]| -> Result<bool, T> {
+        if let Some(&cached) = cache.get(v) {
+            return Ok(cached);
+        }
+        let result = criterion(v)?;
+        cache.insert(v.to_vec(), result);
+        Ok(result)
+    };
+
+    let mut best = start;
+
+    loop {
+        let mut changed = false;
+
+        // Try deleting individual elements.
+        let mut i = 0;
+        while i < best.len() {
+            let mut candidate = best.clone();
+            candidate.remove(i);
+            if check(&candidate)? {
+                best = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        // Shrink each surviving element in place.
+        for i in 0..best.len() {
+            let original = best[i];
+            let mut current = best.clone();
+            let reduced = minimize_integer(original, |x| {
+                current[i] = x;
+                check(&current)
+            })?;
+            if reduced != original {
+                best[i] = reduced;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    Ok(best)
+}
+
+/// Shrinks a failing `f64` toward a "simpler" one: non-negative, integral,
+/// small, with as few mantissa bits set as possible.
+///
+/// Decomposes `start` via its IEEE-754 bit pattern (sign, biased exponent,
+/// mantissa with the implicit leading bit restored) and walks it toward
+/// zero through a sequence of criterion-guarded passes, reusing
+/// `minimize_integer` once the value has become a plain non-negative
+/// integer.
+pub fn minimize_float<F, T>(start: f64, mut criterion: F) -> Result<f64, T>
+where
+    F: FnMut(f64) -> Result<bool, T>,
+{
+    if start.is_nan() || start.is_infinite() {
+        return Ok(start);
+    }
+    if start == 0.0 {
+        if start.is_sign_negative() && criterion(0.0)? {
+            return Ok(0.0);
+        }
+        return Ok(start);
+    }
+
+    let mut best = start;
+
+    // (1) Prefer a non-negative value.
+    if best.is_sign_negative() && criterion(-best)? {
+        best = -best;
+    }
+
+    // (2) Push non-integral values toward their integral neighbours, only
+    // ever accepting a candidate that's actually closer to zero than what
+    // we already have (e.g. for negative `best`, `floor` moves away from
+    // zero and must not clobber a better `trunc`/`ceil` result).
+    if best.fract() != 0.0 && best.abs() <= u64::MAX as f64 {
+        for candidate in [best.trunc(), best.ceil(), best.floor()] {
+            if candidate.abs() < best.abs() && criterion(candidate)? {
+                best = candidate;
+            }
+        }
+    }
+
+    // (3) Once we have a plain non-negative integer, let `minimize_integer`
+    // do the rest and reconstruct the float from the reduced integer.
+    if best.fract() == 0.0 && (0.0..=u64::MAX as f64).contains(&best) {
+        let reduced = minimize_integer(best as u64, |x| criterion(x as f64))?;
+        return Ok(reduced as f64);
+    }
+
+    // (4) Fallback: reduce the mantissa toward zero with the same
+    // halving / bit-toggling passes `Minimizer` uses for integers, keeping
+    // sign and exponent fixed.
+    let bits = best.to_bits();
+    let sign = bits >> 63;
+    let exp = (bits >> 52) & 0x7ff;
+    let mant = bits & 0xf_ffff_ffff_ffff;
+    let full_mant = if exp != 0 {
+        mant | 0x10_0000_0000_0000
+    } else {
+        mant << 1
+    };
+    let unbiased_exp = exp as i32 - 1075;
+
+    let rebuild = |m: u64| -> f64 {
+        let value = (m as f64) * 2f64.powi(unbiased_exp);
+        if sign == 1 {
+            -value
+        } else {
+            value
+        }
+    };
+
+    let mut mant_criterion = |m: &u64| -> Result<bool, T> { criterion(rebuild(*m)) };
+
+    if full_mant == 0 {
+        return Ok(rebuild(full_mant));
+    }
+
+    for i in 0..min(full_mant, SMALL) {
+        if mant_criterion(&i)? {
+            return Ok(rebuild(i));
+        }
+    }
+    if full_mant <= SMALL {
+        return Ok(rebuild(full_mant));
+    }
+
+    let mut minimizer = Minimizer {
+        best: full_mant,
+        key: identity,
+        criterion: &mut mant_criterion,
+    };
+
+    loop {
+        if !minimizer.modify(|x| *x >> 1)? {
+            break;
+        }
+    }
+    for i in 0..64 {
+        minimizer.modify(|x| *x ^ (1 << i))?;
+    }
+
+    Ok(rebuild(minimizer.best))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +401,132 @@ mod tests {
         let n = non_failing_minimize(y, |k| k & x == x);
         assert_eq!(n, x);
     }
+
+    fn non_failing_minimize_towards<F>(start: u64, target: u64, criterion: F) -> u64
+    where
+        F: Fn(u64) -> bool,
+    {
+        let r: Result<u64, ()> = minimize_integer_towards(start, target, |x| Ok(criterion(x)));
+        r.unwrap()
+    }
+
+    #[test]
+    fn minimize_towards_target() {
+        let n = non_failing_minimize_towards(100, 50, |x| x >= 60);
+        assert_eq!(n, 60);
+    }
+
+    #[test]
+    fn minimize_towards_target_from_below() {
+        let n = non_failing_minimize_towards(10, 50, |x| x <= 40);
+        assert_eq!(n, 40);
+    }
+
+    #[test]
+    fn minimize_towards_zero_matches_minimize_integer() {
+        let n = non_failing_minimize_towards(100, 0, |x| x >= 10);
+        assert_eq!(n, 10);
+    }
+
+    fn non_failing_minimize_float<F>(start: f64, criterion: F) -> f64
+    where
+        F: Fn(f64) -> bool,
+    {
+        let r: Result<f64, ()> = minimize_float(start, |x| Ok(criterion(x)));
+        r.unwrap()
+    }
+
+    #[test]
+    fn minimize_float_down_to_integer() {
+        let n = non_failing_minimize_float(100.5, |x| x >= 10.0);
+        assert_eq!(n, 10.0);
+    }
+
+    #[test]
+    fn minimize_float_prefers_non_negative() {
+        let n = non_failing_minimize_float(-10.0, |x| x.abs() >= 10.0);
+        assert_eq!(n, 10.0);
+    }
+
+    #[test]
+    fn minimize_float_negative_non_integral_does_not_regress() {
+        let n = non_failing_minimize_float(-7.5, |x| x <= -7.0);
+        assert_eq!(n, -7.0);
+    }
+
+    #[test]
+    fn minimize_float_huge_value_terminates() {
+        // Regression test: a criterion that stays satisfiable all the way
+        // down to zero must not hang the mantissa fallback pass.
+        let n = non_failing_minimize_float(f64::MAX, |x| x.is_finite());
+        assert_eq!(n, 0.0);
+    }
+
+    fn non_failing_minimize_by<T, Key, Rebuild, F>(
+        start: T,
+        key: Key,
+        rebuild: Rebuild,
+        criterion: F,
+    ) -> T
+    where
+        T: Clone,
+        Key: Fn(&T) -> u64,
+        Rebuild: Fn(&T, u64) -> T,
+        F: Fn(&T) -> bool,
+    {
+        let r: Result<T, ()> = minimize_by(start, key, rebuild, |x| Ok(criterion(x)));
+        r.unwrap()
+    }
+
+    #[test]
+    fn minimize_by_duration_seconds() {
+        use std::time::Duration;
+
+        let n = non_failing_minimize_by(
+            Duration::from_secs(100),
+            |d: &Duration| d.as_secs(),
+            |_, secs| Duration::from_secs(secs),
+            |d: &Duration| d.as_secs() >= 10,
+        );
+        assert_eq!(n, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn minimize_by_stateful_rebuild_does_not_assume_ties_independent_of_cur() {
+        // `rebuild` here carries over non-score state from `cur` (a visit
+        // counter), so candidates built later in the run can satisfy
+        // `criterion` even though the equivalent candidate built from
+        // `start` at the opening scan did not. This must not panic, and the
+        // tie-breaking behavior (accept on first strict score improvement,
+        // don't re-run `criterion` on score ties) must still hold.
+        let n = non_failing_minimize_by(
+            (20u64, 0u64),
+            |t: &(u64, u64)| t.0,
+            |cur, score| (score, cur.1 + 1),
+            |t: &(u64, u64)| (t.1 >= 1 && t.0 == 10) || (t.1 >= 2 && t.0 == 2),
+        );
+        assert_eq!(n, (2, 2));
+    }
+
+    fn non_failing_minimize_integers<F>(start: Vec<u64>, criterion: F) -> Vec<u64>
+    where
+        F: Fn(&[u64]) -> bool,
+    {
+        let r: Result<Vec<u64>, ()> = minimize_integers(start, |v| Ok(criterion(v)));
+        r.unwrap()
+    }
+
+    #[test]
+    fn minimize_integers_shrinks_elements() {
+        let n = non_failing_minimize_integers(vec![100, 100, 100], |v| {
+            v.len() == 3 && v.iter().all(|&x| x >= 10)
+        });
+        assert_eq!(n, vec![10, 10, 10]);
+    }
+
+    #[test]
+    fn minimize_integers_drops_unneeded_elements() {
+        let n = non_failing_minimize_integers(vec![1, 2, 3, 4, 5], |v| v.len() >= 2);
+        assert_eq!(n.len(), 2);
+    }
 }